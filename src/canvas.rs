@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+
 use macroquad::prelude::*;
 use glam::Vec2;
 
@@ -10,6 +12,11 @@ pub struct Box {
     p2: Point,
 }
 impl Box {
+    /// Build a box spanning `p1` and `p2`, in either order.
+    pub fn new(p1: Point, p2: Point) -> Self {
+        Self { p1: p1.min(p2), p2: p1.max(p2) }
+    }
+
     /// Expands exactly as much as needed to contain `p`.
     /// The new region always contains the old one; interior points are never lost.
     pub fn expand_to_contain(&mut self, p: Point) {
@@ -18,6 +25,7 @@ impl Box {
     }
 
     /// Returns true iff `p` is in the box (boundary included).
+    #[allow(dead_code)] // symmetric with `Rectangle::contains`; kept for API parity, no internal caller yet
     pub fn contains(&self, p: Point) -> bool {
         self.p1.x <= p.x &&
         self.p1.y <= p.y &&
@@ -31,6 +39,7 @@ impl Box {
 /// `a` defines the position of one corner.
 /// `ab` and `ad` are an orthogonal pair of vectors defining the sides of the rectangle adjacent to a.
 // @todo determine whether the sides should be allowed to be 0 or non-orthogonal
+#[derive(Clone, Copy)]
 pub struct Rectangle {
     a: Point,
     ab: Vec2,
@@ -71,30 +80,77 @@ impl Rectangle {
         let a = p - 0.5 * ad;
         Self { a, ab, ad }
     }
+
+    /// The four corners of the rectangle, in `abcda` order.
+    fn corners(&self) -> [Point; 4] {
+        [self.a, self.a + self.ab, self.a + self.ab + self.ad, self.a + self.ad]
+    }
+
+    /// Does this rectangle intersect the axis-aligned `other`?
+    /// Uses the separating axis theorem over the rectangle's two edge directions and the box's two
+    /// (trivial) edge directions: the shapes overlap iff their projections onto every one of those axes
+    /// overlap.
+    pub fn intersects(&self, other: &Box) -> bool {
+        let box_corners = [
+            other.p1,
+            Point::new(other.p2.x, other.p1.y),
+            other.p2,
+            Point::new(other.p1.x, other.p2.y),
+        ];
+        let rect_corners = self.corners();
+
+        // a zero-width rectangle (e.g. the oriented box of a straight stroke) still needs the axis
+        // perpendicular to `ab`, or it would match boxes lying beside the segment
+        let ad_axis = if self.ad == Vec2::ZERO { self.ab.perp() } else { self.ad };
+        let axes = [self.ab.normalize_or_zero(), ad_axis.normalize_or_zero(), Vec2::X, Vec2::Y];
+        let project = |points: &[Point], axis: Vec2| -> (f32, f32) {
+            points.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), p| {
+                let d = p.dot(axis);
+                (min.min(d), max.max(d))
+            })
+        };
+
+        axes.into_iter().filter(|axis| *axis != Vec2::ZERO).all(|axis| {
+            let (rect_min, rect_max) = project(&rect_corners, axis);
+            let (box_min, box_max) = project(&box_corners, axis);
+            rect_max >= box_min && box_max >= rect_min
+        })
+    }
+}
+
+/// Which points inside a closed curve's polygon count as "inside" when filling it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    /// A point is inside iff a ray cast from it crosses the polygon boundary an odd number of times.
+    EvenOdd,
+    /// A point is inside iff the polygon's signed winding number around it is nonzero.
+    NonZero,
 }
 
 /// A sequence of points. No thickness information attached!
 /// The bounding box is provided to easily find curves intersecting a region; you can first check whether the
 /// bounding box intersect the region before checking every point on the curve, which allows you to quickly
 /// discard curves that are not near the region.
-/* @todo would it be more efficient to compute a bounding rectangle of minimal area? E.g. a diagonal line
-segment is much more precisely constrained by a rotated rectangle than a horizontal rectangle. This would
-reduce the number of curves whose bounding box intersect the index region. Some questions:
-- Is this efficient to compute?
-- Is this efficient to compute iteratively? (i.e. every time a new point is added to the curve)
-    Although, why would we ever need to compute it iteratively? New points are only added while the curve is
-    being drawn.
-- Is it easy to compute the intersection of a regular box with a rotated rectangle?
-*/
 pub struct Curve {
     points: Vec<Point>,
     bounding_box: Box,
+    /// Whether this curve is a closed polygon that should be filled, and if so, under which rule.
+    closed: Option<FillRule>,
+    /// Memoized minimum-area oriented bounding rectangle; see `Curve::oriented_bounding_box`.
+    /// Invalidated whenever `points` changes.
+    cached_obb: RefCell<Option<Rectangle>>,
+    /// Memoized result of the last `fit_beziers` call, keyed on the `error_tol` it was computed with.
+    /// Invalidated whenever `points` changes.
+    cached_fit: RefCell<Option<(f32, Vec<CubicBezier>)>>,
 }
 impl Curve {
     pub fn new(first_point: Point) -> Self {
         Self {
             points: vec![first_point],
             bounding_box: Box { p1: first_point, p2: first_point },
+            closed: None,
+            cached_obb: RefCell::new(None),
+            cached_fit: RefCell::new(None),
         }
     }
 
@@ -102,12 +158,416 @@ impl Curve {
         self.points.push(p);
         // update the bounding box
         self.bounding_box.expand_to_contain(p);
+        *self.cached_obb.borrow_mut() = None;
+        *self.cached_fit.borrow_mut() = None;
+    }
+
+    /// The minimum-area rectangle (of any orientation) enclosing every stored point.
+    /// Tighter than `bounding_box` for diagonal strokes, which cuts down on false-positive region
+    /// intersections. Computed via rotating calipers over the convex hull: the optimal rectangle always
+    /// has one side collinear with a hull edge, so trying every hull edge and keeping the smallest-area
+    /// result is exact.
+    pub fn oriented_bounding_box(&self) -> Rectangle {
+        if let Some(cached) = *self.cached_obb.borrow() { return cached; }
+
+        let rect = min_area_rectangle(&convex_hull(&self.points));
+        *self.cached_obb.borrow_mut() = Some(rect);
+        rect
+    }
+
+    /// Fit the stored points with a sequence of cubic Bézier curves, each within `error_tol`
+    /// (squared distance) of the original points.
+    ///
+    /// This follows Schneider's curve-fitting algorithm (Graphics Gems I): tangents are estimated at the
+    /// endpoints from their neighbors, a single cubic is least-squares fit along those tangents using the
+    /// chord-length parameterization of the points, and, if the fit isn't within tolerance, the curve is
+    /// split at its point of worst fit and the two halves are fit recursively.
+    pub fn fit_beziers(&self, error_tol: f32) -> Vec<CubicBezier> {
+        if let Some((cached_tol, cached)) = &*self.cached_fit.borrow() {
+            if *cached_tol == error_tol { return cached.clone(); }
+        }
+
+        let fitted = if self.points.len() < 2 {
+            Vec::new()
+        } else {
+            let t_hat1 = estimate_tangent(&self.points, 0, 1);
+            let t_hat2 = estimate_tangent(&self.points, self.points.len() - 1, self.points.len() - 2);
+            fit_cubic(&self.points, t_hat1, t_hat2, error_tol)
+        };
+
+        *self.cached_fit.borrow_mut() = Some((error_tol, fitted.clone()));
+        fitted
+    }
+
+    /// Render this curve as a centripetal Catmull-Rom spline through its stored points, sampling
+    /// `points_per_segment` points per interior segment. Curves with fewer than 4 points can't form a
+    /// spline segment, so their points are returned unchanged (a straight-line path).
+    fn smoothed_points(&self, points_per_segment: usize) -> Vec<Point> {
+        if self.points.len() < 4 { return self.points.clone(); }
+
+        // duplicate the endpoints so the first and last real segments have a full quadruple to draw from
+        let mut extended = Vec::with_capacity(self.points.len() + 2);
+        extended.push(self.points[0]);
+        extended.extend_from_slice(&self.points);
+        extended.push(*self.points.last().unwrap());
+
+        let mut out = Vec::new();
+        for quad in extended.windows(4) {
+            catmull_rom_segment(quad[0], quad[1], quad[2], quad[3], points_per_segment, &mut out);
+        }
+        out.push(*self.points.last().unwrap());
+        out
+    }
+
+    /// Build a curve from an already-known point list, recomputing its bounding box.
+    /// Panics if `points` is empty.
+    fn from_points(points: Vec<Point>) -> Self {
+        let mut bounding_box = Box { p1: points[0], p2: points[0] };
+        for &p in &points[1..] { bounding_box.expand_to_contain(p); }
+        Self {
+            points,
+            bounding_box,
+            closed: None,
+            cached_obb: RefCell::new(None),
+            cached_fit: RefCell::new(None),
+        }
     }
 
-    // /// Return the new set of curves that results from erasing every point in `region`
-    // fn erase(region: Box) {
-        
-    // }
+    /// Return the curves that result from erasing every point of `self` that falls within `region`.
+    /// Erasing through the middle of a stroke yields two curves; erasing all of it yields none.
+    fn erase(&self, region: &Rectangle) -> Vec<Curve> {
+        let mut result = Vec::new();
+        let mut run: Vec<Point> = Vec::new();
+
+        for &p in &self.points {
+            if region.contains(p) {
+                // flush a surviving run, or drop it if it's too short to form its own curve; either way it
+                // must not linger, or a run that dips back into `region` would bridge the erased gap
+                if run.len() >= 2 { result.push(Curve::from_points(std::mem::take(&mut run))); }
+                else { run.clear(); }
+            } else {
+                run.push(p);
+            }
+        }
+        if run.len() >= 2 { result.push(Curve::from_points(run)); }
+
+        result
+    }
+
+    /// The `d` attribute of this curve's SVG `<path>`: `M` to the first point, then `C` commands along
+    /// its Bézier fit. A single-point curve has no fit, so its path is just the `M`.
+    fn to_svg_path_data(&self) -> String {
+        const BEZIER_ERROR_TOLERANCE: f32 = 4.0;
+
+        let beziers = self.fit_beziers(BEZIER_ERROR_TOLERANCE);
+        let mut d = String::new();
+
+        if let Some(first) = beziers.first() {
+            d.push_str(&format!("M{} {}", first.p0.x, first.p0.y));
+            for bezier in &beziers {
+                let c = [bezier.p1, bezier.p2, bezier.p3];
+                d.push_str(&format!(" C{} {}, {} {}, {} {}", c[0].x, c[0].y, c[1].x, c[1].y, c[2].x, c[2].y));
+            }
+        } else if let Some(&first) = self.points.first() {
+            d.push_str(&format!("M{} {}", first.x, first.y));
+            for p in &self.points[1..] {
+                d.push_str(&format!(" L{} {}", p.x, p.y));
+            }
+        }
+
+        d
+    }
+
+    /// Parse a `d` attribute produced by `to_svg_path_data` back into a `Curve`.
+    /// `C` commands are resampled into points so the curve still supports point-level operations like
+    /// erasure.
+    fn from_svg_path_data(d: &str) -> Result<Curve, ParseError> {
+        const SAMPLES_PER_BEZIER: usize = 16;
+
+        let mut points: Vec<Point> = Vec::new();
+        let mut command = ' ';
+        let mut chunk_start = 0;
+        let mut chunks: Vec<(char, &str)> = Vec::new();
+
+        for (i, c) in d.char_indices() {
+            if matches!(c, 'M' | 'L' | 'C') {
+                if i > chunk_start { chunks.push((command, &d[chunk_start..i])); }
+                command = c;
+                chunk_start = i + 1;
+            }
+        }
+        chunks.push((command, &d[chunk_start..]));
+
+        for (command, arg_str) in chunks {
+            let numbers = arg_str
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|tok| !tok.is_empty())
+                .map(|tok| tok.parse::<f32>().map_err(|e| ParseError(format!("bad number {tok:?}: {e}"))))
+                .collect::<Result<Vec<f32>, ParseError>>()?;
+
+            match command {
+                // a real editor's `d` may repeat a command's coordinate pair/sextet without re-emitting the
+                // letter (implicit repetition), so walk `numbers` in chunks rather than expecting exactly one
+                'M' | 'L' => {
+                    if numbers.is_empty() || numbers.len() % 2 != 0 {
+                        return Err(ParseError(format!("{command} expects a multiple of 2 numbers, got {numbers:?}")));
+                    }
+                    for pair in numbers.chunks_exact(2) {
+                        points.push(Point::new(pair[0], pair[1]));
+                    }
+                }
+                'C' => {
+                    if numbers.is_empty() || numbers.len() % 6 != 0 {
+                        return Err(ParseError(format!("C expects a multiple of 6 numbers, got {numbers:?}")));
+                    }
+                    for sextet in numbers.chunks_exact(6) {
+                        let p0 = *points.last().ok_or_else(|| ParseError("C with no preceding point".into()))?;
+                        let bezier = CubicBezier {
+                            p0,
+                            p1: Point::new(sextet[0], sextet[1]),
+                            p2: Point::new(sextet[2], sextet[3]),
+                            p3: Point::new(sextet[4], sextet[5]),
+                        };
+                        for i in 1..=SAMPLES_PER_BEZIER {
+                            points.push(bezier.eval(i as f32 / SAMPLES_PER_BEZIER as f32));
+                        }
+                    }
+                }
+                _ => return Err(ParseError(format!("unsupported path command {command:?}"))),
+            }
+        }
+
+        if points.is_empty() { return Err(ParseError("path has no points".into())); }
+        Ok(Curve::from_points(points))
+    }
+}
+
+/// A single cubic Bézier segment, as produced by `Curve::fit_beziers`.
+#[derive(Clone, Copy, Debug)]
+pub struct CubicBezier {
+    pub p0: Point,
+    pub p1: Point,
+    pub p2: Point,
+    pub p3: Point,
+}
+impl CubicBezier {
+    /// Evaluate the curve at parameter `t` in `[0, 1]` via de Casteljau's algorithm.
+    fn eval(&self, t: f32) -> Point {
+        let p01 = self.p0.lerp(self.p1, t);
+        let p12 = self.p1.lerp(self.p2, t);
+        let p23 = self.p2.lerp(self.p3, t);
+        p01.lerp(p12, t).lerp(p12.lerp(p23, t), t)
+    }
+
+    /// First derivative (unnormalized tangent) at parameter `t`.
+    fn deriv(&self, t: f32) -> Vec2 {
+        let d01 = self.p1 - self.p0;
+        let d12 = self.p2 - self.p1;
+        let d23 = self.p3 - self.p2;
+        3.0 * d01.lerp(d12, t).lerp(d12.lerp(d23, t), t)
+    }
+
+    /// Second derivative at parameter `t`.
+    fn deriv2(&self, t: f32) -> Vec2 {
+        let d01 = self.p1 - self.p0;
+        let d12 = self.p2 - self.p1;
+        let d23 = self.p3 - self.p2;
+        6.0 * (d12 - d01).lerp(d23 - d12, t)
+    }
+}
+
+/// Unit vector pointing from `points[i]` towards its neighbor `points[neighbor]`.
+/// Used to estimate the tangent direction at a curve's endpoint.
+fn estimate_tangent(points: &[Point], i: usize, neighbor: usize) -> Vec2 {
+    (points[neighbor] - points[i]).normalize_or_zero()
+}
+
+/// Tangent direction at an interior split point, averaged from its two neighbors.
+fn estimate_center_tangent(points: &[Point], i: usize) -> Vec2 {
+    let incoming = points[i - 1] - points[i];
+    let outgoing = points[i] - points[i + 1];
+    ((incoming + outgoing) * 0.5).normalize_or_zero()
+}
+
+/// Chord-length parameterization of `points`: a monotonic `u` in `[0, 1]` per point, proportional to
+/// distance traveled along the polyline.
+fn chord_length_parameterize(points: &[Point]) -> Vec<f32> {
+    let mut u = Vec::with_capacity(points.len());
+    u.push(0f32);
+    for pair in points.windows(2) {
+        u.push(u.last().unwrap() + pair[1].distance(pair[0]));
+    }
+    let total = *u.last().unwrap();
+    if total > 0.0 {
+        for ui in u.iter_mut() { *ui /= total; }
+    }
+    u
+}
+
+/// Least-squares fit a single cubic Bézier to `points` under parameterization `u`, with its interior
+/// control points constrained to lie along `t_hat1` and `t_hat2`. Solves the 2x2 system for the tangent
+/// magnitudes (see Schneider, Graphics Gems I).
+fn generate_bezier(points: &[Point], u: &[f32], t_hat1: Vec2, t_hat2: Vec2) -> CubicBezier {
+    let p0 = points[0];
+    let p3 = *points.last().unwrap();
+
+    let mut c = [[0f32; 2]; 2];
+    let mut x = [0f32; 2];
+
+    for (i, &ui) in u.iter().enumerate() {
+        let b0 = (1.0 - ui).powi(3);
+        let b1 = 3.0 * ui * (1.0 - ui).powi(2);
+        let b2 = 3.0 * ui.powi(2) * (1.0 - ui);
+        let b3 = ui.powi(3);
+
+        let a1 = t_hat1 * b1;
+        let a2 = t_hat2 * b2;
+
+        c[0][0] += a1.dot(a1);
+        c[0][1] += a1.dot(a2);
+        c[1][1] += a2.dot(a2);
+
+        let shortfall = points[i] - (p0 * (b0 + b1) + p3 * (b2 + b3));
+        x[0] += a1.dot(shortfall);
+        x[1] += a2.dot(shortfall);
+    }
+    c[1][0] = c[0][1];
+
+    let det_c0_c1 = c[0][0] * c[1][1] - c[1][0] * c[0][1];
+    let seg_length = p3.distance(p0);
+
+    let (alpha_l, alpha_r) = if det_c0_c1.abs() > 1e-9 {
+        let det_c0_x = c[0][0] * x[1] - c[1][0] * x[0];
+        let det_x_c1 = x[0] * c[1][1] - x[1] * c[0][1];
+        (det_x_c1 / det_c0_c1, det_c0_x / det_c0_c1)
+    } else {
+        (0.0, 0.0)
+    };
+
+    // a degenerate or overshooting fit falls back to placing the control points a third of the
+    // chord length along the tangents, same as the trivial two-point case
+    let epsilon = 1.0e-6 * seg_length;
+    let (alpha_l, alpha_r) = if alpha_l < epsilon || alpha_r < epsilon {
+        (seg_length / 3.0, seg_length / 3.0)
+    } else {
+        (alpha_l, alpha_r)
+    };
+
+    CubicBezier { p0, p1: p0 + t_hat1 * alpha_l, p2: p3 + t_hat2 * alpha_r, p3 }
+}
+
+/// Squared distance from the worst-fit point to `bezier`, and that point's index in `points`.
+fn compute_max_error(points: &[Point], bezier: &CubicBezier, u: &[f32]) -> (f32, usize) {
+    let mut max_dist = 0.0;
+    let mut split_point = points.len() / 2;
+    for (i, &ui) in u.iter().enumerate() {
+        let dist = bezier.eval(ui).distance_squared(points[i]);
+        if dist > max_dist {
+            max_dist = dist;
+            split_point = i;
+        }
+    }
+    (max_dist, split_point)
+}
+
+/// One Newton-Raphson iteration per point, refining each `u[i]` to be closer to the true closest
+/// parameter on `bezier` for `points[i]`.
+fn reparameterize(points: &[Point], u: &[f32], bezier: &CubicBezier) -> Vec<f32> {
+    points.iter().zip(u.iter()).map(|(&p, &ui)| {
+        let q = bezier.eval(ui);
+        let q1 = bezier.deriv(ui);
+        let q2 = bezier.deriv2(ui);
+
+        let numerator = (q - p).dot(q1);
+        let denominator = q1.dot(q1) + (q - p).dot(q2);
+
+        if denominator.abs() < 1e-9 { ui } else { ui - numerator / denominator }
+    }).collect()
+}
+
+/// Fit `points` (with endpoint tangents `t_hat1`, `t_hat2`) to cubic Béziers that each stay within
+/// `error_tol` (squared distance) of the points they cover, splitting only where a single cubic doesn't
+/// fit. The split point is chosen greedily, so the number of cubics is not guaranteed to be the fewest.
+fn fit_cubic(points: &[Point], t_hat1: Vec2, t_hat2: Vec2, error_tol: f32) -> Vec<CubicBezier> {
+    // trivial case: a single segment needs no least-squares fit, just lay the control points out
+    // along the given tangents at a third of the chord length
+    if points.len() == 2 {
+        let dist = points[1].distance(points[0]) / 3.0;
+        return vec![CubicBezier {
+            p0: points[0],
+            p1: points[0] + t_hat1 * dist,
+            p2: points[1] + t_hat2 * dist,
+            p3: points[1],
+        }];
+    }
+
+    let u = chord_length_parameterize(points);
+    let mut bezier = generate_bezier(points, &u, t_hat1, t_hat2);
+    let (mut max_error, mut split_point) = compute_max_error(points, &bezier, &u);
+
+    if max_error >= error_tol {
+        // one Newton-Raphson reparameterization pass, then a refit under the corrected `u`, in case
+        // that brings the fit within tolerance without splitting
+        let reparam_u = reparameterize(points, &u, &bezier);
+        let reparam_bezier = generate_bezier(points, &reparam_u, t_hat1, t_hat2);
+        let (reparam_error, reparam_split) = compute_max_error(points, &reparam_bezier, &reparam_u);
+
+        if reparam_error < max_error {
+            bezier = reparam_bezier;
+            max_error = reparam_error;
+            split_point = reparam_split;
+        }
+    }
+
+    if max_error < error_tol { return vec![bezier]; }
+
+    // still too far off: split at the point of worst fit and recurse on both halves
+    let split_point = split_point.clamp(1, points.len() - 2);
+    let center_tangent = estimate_center_tangent(points, split_point);
+
+    let mut fitted = fit_cubic(&points[..=split_point], t_hat1, center_tangent, error_tol);
+    fitted.extend(fit_cubic(&points[split_point..], -center_tangent, t_hat2, error_tol));
+    fitted
+}
+
+/// Sample `points_per_segment` points of the centripetal Catmull-Rom spline between `p1` and `p2`, using
+/// `p0` and `p3` to shape the tangents at either end, and append them to `out`.
+///
+/// Uses the Barry-Goldman pyramid: knot values `t0..t3` are spaced by `|P_{i+1} - P_i|^alpha` with the
+/// centripetal exponent `alpha = 0.5`, which (unlike the uniform parameterization) avoids cusps and
+/// self-intersections on fast, uneven hand strokes.
+fn catmull_rom_segment(p0: Point, p1: Point, p2: Point, p3: Point, points_per_segment: usize, out: &mut Vec<Point>) {
+    const ALPHA: f32 = 0.5;
+
+    let t0 = 0f32;
+    let t1 = t0 + p1.distance(p0).powf(ALPHA);
+    let t2 = t1 + p2.distance(p1).powf(ALPHA);
+    let t3 = t2 + p3.distance(p2).powf(ALPHA);
+
+    // a zero-length segment between `p1` and `p2` has nothing to sample; just hold position instead
+    if t2 == t1 {
+        for _ in 0..points_per_segment { out.push(p1); }
+        return;
+    }
+
+    // a zero-length outer span (e.g. a duplicated endpoint, `p0 == p1`) has the same point at both
+    // ends, so interpolating over it yields that point
+    let lerp = |a: Point, b: Point, ta: f32, tb: f32, t: f32| {
+        if tb == ta { a } else { a + (b - a) * ((t - ta) / (tb - ta)) }
+    };
+
+    for i in 0..points_per_segment {
+        let t = t1 + (t2 - t1) * (i as f32 / points_per_segment as f32);
+
+        let a1 = lerp(p0, p1, t0, t1, t);
+        let a2 = lerp(p1, p2, t1, t2, t);
+        let a3 = lerp(p2, p3, t2, t3, t);
+
+        let b1 = lerp(a1, a2, t0, t2, t);
+        let b2 = lerp(a2, a3, t1, t3, t);
+
+        out.push(lerp(b1, b2, t1, t2, t));
+    }
 }
 
 /// Error returned when starting a new stroke when one is already in progress.
@@ -117,18 +577,102 @@ pub struct AlreadyExists;
 /// but the current stroke doesn't exist.
 #[derive(Debug)]
 pub struct DoesntExist;
+/// Error returned when a string isn't a valid canvas SVG document; carries a human-readable reason.
+#[derive(Debug)]
+pub struct ParseError(String);
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { write!(f, "{}", self.0) }
+}
+
+/// Number of spline samples drawn per interior segment of a smoothed curve. See `Curve::smoothed_points`.
+const DEFAULT_POINTS_PER_SEGMENT: usize = 16;
+
+/// Width of the quad swept along the eraser's path; see `Canvas::continue_erasure`.
+const ERASER_WIDTH: f32 = 20.0;
 
+/// Tracks an in-progress erasure: just enough state to sweep a `Rectangle` quad from the last point to
+/// the next one as the user drags.
+struct Eraser {
+    last_point: Point,
+}
+
+/// Side length of a `SpatialIndex` grid cell, in world units.
+const SPATIAL_INDEX_CELL_SIZE: f32 = 256.0;
+
+/// A uniform grid over world coordinates, mapping each cell to the indices (into `Canvas::curves`) of
+/// every curve whose bounding box overlaps it. Lets region queries (erasure, hit-testing) only scan
+/// curves near the region instead of the whole canvas.
 #[derive(Default)]
+struct SpatialIndex {
+    cells: std::collections::HashMap<(i32, i32), Vec<usize>>,
+}
+impl SpatialIndex {
+    fn cell_coords(p: Point) -> (i32, i32) {
+        ((p.x / SPATIAL_INDEX_CELL_SIZE).floor() as i32, (p.y / SPATIAL_INDEX_CELL_SIZE).floor() as i32)
+    }
+
+    /// Every grid cell overlapped by `b`.
+    fn cells_for(b: &Box) -> impl Iterator<Item = (i32, i32)> {
+        let (min_x, min_y) = Self::cell_coords(b.p1);
+        let (max_x, max_y) = Self::cell_coords(b.p2);
+        (min_x..=max_x).flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+    }
+
+    fn insert(&mut self, curve_index: usize, bounding_box: &Box) {
+        for cell in Self::cells_for(bounding_box) {
+            self.cells.entry(cell).or_default().push(curve_index);
+        }
+    }
+
+    fn clear(&mut self) { self.cells.clear(); }
+
+    /// Candidate curve indices whose cells overlap `region`, deduplicated. These are only candidates:
+    /// the cell grid is coarser than a curve's exact bounding box, so callers still need to check the
+    /// real intersection before treating a candidate as a hit.
+    fn candidates(&self, region: &Box) -> Vec<usize> {
+        let mut candidates: Vec<usize> = Self::cells_for(region)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+}
+
 pub struct Canvas {
     curves: Vec<Curve>,
     current_curve: Option<Curve>,
+    current_erasure: Option<Eraser>,
+    index: SpatialIndex,
+    /// Resolution of the Catmull-Rom spline used to render curves; see `Curve::smoothed_points`.
+    points_per_segment: usize,
+}
+impl Default for Canvas {
+    fn default() -> Self {
+        Self {
+            curves: Vec::new(),
+            current_curve: None,
+            current_erasure: None,
+            index: SpatialIndex::default(),
+            points_per_segment: DEFAULT_POINTS_PER_SEGMENT,
+        }
+    }
 }
 impl Canvas {
     /// Render a single curve.
     /// This can be used to render curves that aren't strictly part of the canvas yet, such as a stroke that
     /// the user is in the process of drawing.
-    fn render_curve(curve: &Curve) {
-        for endpoints in curve.points.windows(2) {
+    fn render_curve(curve: &Curve, points_per_segment: usize) {
+        if let Some(fill_rule) = curve.closed {
+            for triangle in tessellate_fill(&curve.points, fill_rule) {
+                draw_triangle(triangle[0], triangle[1], triangle[2], BLUE);
+            }
+        }
+
+        let smoothed = curve.smoothed_points(points_per_segment);
+        for endpoints in smoothed.windows(2) {
             let p1 = endpoints[0];
             let p2 = endpoints[1];
             draw_line(p1.x, p1.y, p2.x, p2.y, 3f32, BLUE);
@@ -141,11 +685,11 @@ impl Canvas {
         //
         draw_rectangle_lines(x1, y1, x2 - x1, y2 - y1, 2f32, RED);
     }
-    
+
     /// Render all objects on the canvas to the screen.
     pub fn render(&self) {
-        for curve in &self.curves { Self::render_curve(curve); }
-        if let Some(ref curve) = self.current_curve { Self::render_curve(curve); }
+        for curve in &self.curves { Self::render_curve(curve, self.points_per_segment); }
+        if let Some(ref curve) = self.current_curve { Self::render_curve(curve, self.points_per_segment); }
     }
 
     /// Start drawing a stroke on the canvas.
@@ -171,6 +715,7 @@ impl Canvas {
     /// Returns an error if there is no stroke in progress.
     pub fn end_stroke(&mut self) -> Result<(), DoesntExist> {
         if let Some(curve) = self.current_curve.take() {
+            self.index.insert(self.curves.len(), &curve.bounding_box);
             self.curves.push(curve);
             Ok(())
         }
@@ -179,4 +724,322 @@ impl Canvas {
     /// Is a stroke currently being drawn on the canvas?
     /// True iff the latest stroke created by `begin_stroke` hasn't yet been ended via `end_stroke`.
     pub fn is_stroke_in_progress(&self) -> bool { self.current_curve.is_some() }
+
+    /// Mark the most recently committed curve as a closed polygon and fill it under `fill_rule`, e.g. a
+    /// lasso selection or a hand-drawn closed glyph.
+    /// Returns an error if there are no committed curves.
+    pub fn close_last_curve(&mut self, fill_rule: FillRule) -> Result<(), DoesntExist> {
+        if let Some(curve) = self.curves.last_mut() {
+            curve.closed = Some(fill_rule);
+            Ok(())
+        }
+        else { Err(DoesntExist) }
+    }
+
+    /// Start erasing from `first_point`.
+    /// Returns an error if there is already an erasure in progress.
+    pub fn begin_erasure(&mut self, first_point: Point) -> Result<(), AlreadyExists> {
+        if self.current_erasure.is_some() { Err(AlreadyExists) }
+        else {
+            self.current_erasure = Some(Eraser { last_point: first_point });
+            Ok(())
+        }
+    }
+    /// Sweep the eraser to `p`, immediately erasing every committed curve it passes through.
+    /// Returns an error if there is no erasure in progress.
+    pub fn continue_erasure(&mut self, p: Point) -> Result<(), DoesntExist> {
+        if let Some(eraser) = &mut self.current_erasure {
+            // a zero-length segment would sweep a zero-area quad, whose `ab`/`ad` are both `Vec2::ZERO`;
+            // `Rectangle::contains` then holds trivially for every point, erasing the whole canvas
+            if p != eraser.last_point {
+                let quad = Rectangle::along_line_segment(eraser.last_point, p - eraser.last_point, ERASER_WIDTH);
+                eraser.last_point = p;
+                self.erase_region(&quad);
+            }
+            Ok(())
+        }
+        else { Err(DoesntExist) }
+    }
+    /// Finish erasing.
+    /// Returns an error if there is no erasure in progress.
+    pub fn end_erasure(&mut self) -> Result<(), DoesntExist> {
+        if self.current_erasure.take().is_some() { Ok(()) } else { Err(DoesntExist) }
+    }
+    /// Is an erasure currently in progress?
+    /// True iff the latest erasure created by `begin_erasure` hasn't yet been ended via `end_erasure`.
+    pub fn is_erasure_in_progress(&self) -> bool { self.current_erasure.is_some() }
+
+    /// Erase every stored point of every committed curve that falls within `region`, replacing curves
+    /// cut through the middle with the surviving runs on either side.
+    fn erase_region(&mut self, region: &Rectangle) {
+        let region_box = bounding_box_of_points(&region.corners());
+
+        let to_erase: Vec<usize> = self.index.candidates(&region_box).into_iter()
+            .filter(|&i| region.intersects(&self.curves[i].bounding_box))
+            // a curve with no point inside `region` is left as it is
+            .filter(|&i| self.curves[i].points.iter().any(|&p| region.contains(p)))
+            .collect();
+
+        // remove highest indices first so swap_remove never disturbs an index still queued to erase
+        let mut survivors = Vec::new();
+        for i in to_erase.into_iter().rev() {
+            let curve = self.curves.swap_remove(i);
+            survivors.extend(curve.erase(region));
+        }
+        self.curves.extend(survivors);
+
+        // indices shifted under swap_remove, so the cheapest correct fix-up is a full rebuild
+        self.rebuild_index();
+    }
+
+    fn rebuild_index(&mut self) {
+        self.index.clear();
+        for (i, curve) in self.curves.iter().enumerate() {
+            self.index.insert(i, &curve.bounding_box);
+        }
+    }
+
+    /// Serialize every committed curve as an SVG `<path>`, giving a portable, inspectable storage format
+    /// that `from_svg` reads back. Curves come back resampled from their Bézier fits, and whether a curve
+    /// is closed and filled is not saved.
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::from("<svg xmlns=\"http://www.w3.org/2000/svg\">\n");
+        for curve in &self.curves {
+            svg.push_str("  <path d=\"");
+            svg.push_str(&curve.to_svg_path_data());
+            svg.push_str("\"/>\n");
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Parse an SVG document previously produced by `to_svg` back into a `Canvas`.
+    pub fn from_svg(svg: &str) -> Result<Canvas, ParseError> {
+        let mut canvas = Canvas::default();
+        for path_data in extract_path_data(svg) {
+            canvas.curves.push(Curve::from_svg_path_data(&path_data)?);
+        }
+        canvas.rebuild_index();
+        Ok(canvas)
+    }
+
+    /// Curves whose oriented bounding box intersects `region`, without scanning curves the spatial index
+    /// can already rule out. Uses each curve's minimal-area oriented box rather than its axis-aligned one,
+    /// so diagonal strokes don't spuriously match a region they don't actually come near.
+    pub fn curves_intersecting<'a>(&'a self, region: &'a Box) -> impl Iterator<Item = &'a Curve> {
+        self.index.candidates(region).into_iter()
+            .filter_map(move |i| self.curves.get(i))
+            .filter(move |curve| curve.oriented_bounding_box().intersects(region))
+    }
+}
+
+/// Convex hull of `points`, in counterclockwise order, via the monotone chain algorithm.
+fn convex_hull(points: &[Point]) -> Vec<Point> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap().then(a.y.partial_cmp(&b.y).unwrap()));
+    sorted.dedup();
+    if sorted.len() < 3 { return sorted; }
+
+    fn cross(o: Point, a: Point, b: Point) -> f32 {
+        (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+    }
+
+    let mut lower: Vec<Point> = Vec::new();
+    for &p in &sorted {
+        while lower.len() >= 2 && cross(lower[lower.len() - 2], lower[lower.len() - 1], p) <= 0.0 {
+            lower.pop();
+        }
+        lower.push(p);
+    }
+
+    let mut upper: Vec<Point> = Vec::new();
+    for &p in sorted.iter().rev() {
+        while upper.len() >= 2 && cross(upper[upper.len() - 2], upper[upper.len() - 1], p) <= 0.0 {
+            upper.pop();
+        }
+        upper.push(p);
+    }
+
+    lower.pop();
+    upper.pop();
+    lower.extend(upper);
+    lower
+}
+
+/// Minimum-area rectangle (any orientation) enclosing the convex polygon `hull`, via rotating calipers:
+/// the optimal rectangle always has one side collinear with a hull edge, so every edge is tried as that
+/// side and the smallest-area result wins. Returned in the same coordinate space as `hull`.
+fn min_area_rectangle(hull: &[Point]) -> Rectangle {
+    // a single point, or a collinear point set (whose hull collapses to its two extremes): there's no
+    // edge to rotate calipers around, so build a zero-width rectangle aligned with the point(s) instead
+    // of falling back to an axis-aligned box, which would be needlessly loose for e.g. a straight stroke
+    if hull.len() < 3 {
+        let a = hull[0];
+        let ab = hull.last().map_or(Vec2::ZERO, |&last| last - a);
+        return Rectangle { a, ab, ad: Vec2::ZERO };
+    }
+
+    let mut best_area = f32::INFINITY;
+    let mut best_rect = Rectangle { a: hull[0], ab: Vec2::ZERO, ad: Vec2::ZERO };
+
+    for i in 0..hull.len() {
+        let edge_dir = (hull[(i + 1) % hull.len()] - hull[i]).normalize_or_zero();
+        if edge_dir == Vec2::ZERO { continue; }
+        let perp_dir = edge_dir.perp();
+
+        let (mut min_u, mut max_u, mut min_v, mut max_v) =
+            (f32::INFINITY, f32::NEG_INFINITY, f32::INFINITY, f32::NEG_INFINITY);
+        for &p in hull {
+            let (u, v) = (p.dot(edge_dir), p.dot(perp_dir));
+            min_u = min_u.min(u);
+            max_u = max_u.max(u);
+            min_v = min_v.min(v);
+            max_v = max_v.max(v);
+        }
+
+        let area = (max_u - min_u) * (max_v - min_v);
+        if area < best_area {
+            best_area = area;
+            best_rect = Rectangle {
+                a: edge_dir * min_u + perp_dir * min_v,
+                ab: edge_dir * (max_u - min_u),
+                ad: perp_dir * (max_v - min_v),
+            };
+        }
+    }
+
+    best_rect
+}
+
+/// One edge of a closed polygon, reoriented so `y0 <= y1`, for the scanline tessellator below.
+struct PolygonEdge {
+    y0: f32,
+    y1: f32,
+    x0: f32, // x at y0
+    x1: f32, // x at y1
+    /// +1 if the original edge pointed downward (increasing y), -1 if upward. Used for the nonzero rule.
+    winding: f32,
+}
+impl PolygonEdge {
+    fn x_at(&self, y: f32) -> f32 {
+        self.x0 + (self.x1 - self.x0) * (y - self.y0) / (self.y1 - self.y0)
+    }
+
+    fn slope(&self) -> f32 {
+        (self.x1 - self.x0) / (self.y1 - self.y0)
+    }
+}
+
+/// The y within `(y0, y1)` at which `a` and `b` swap x-order, if any. Both are straight lines active
+/// across the whole slice, with no vertex of either inside it, so they cross at most once there.
+fn edge_crossing(a: &PolygonEdge, b: &PolygonEdge, y0: f32, y1: f32) -> Option<f32> {
+    let (ma, mb) = (a.slope(), b.slope());
+    if ma == mb { return None; }
+    let y = (b.x0 - a.x0 + ma * a.y0 - mb * b.y0) / (ma - mb);
+    if y > y0 && y < y1 { Some(y) } else { None }
+}
+
+/// The edges of the closed polygon `points[0] -> points[1] -> ... -> points[0]`.
+/// Horizontal edges are dropped: they never become active on a scanline.
+fn polygon_edges(points: &[Point]) -> Vec<PolygonEdge> {
+    let n = points.len();
+    (0..n).filter_map(|i| {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        if a.y == b.y { None }
+        else if a.y < b.y { Some(PolygonEdge { y0: a.y, y1: b.y, x0: a.x, x1: b.x, winding: 1.0 }) }
+        else { Some(PolygonEdge { y0: b.y, y1: a.y, x0: b.x, x1: a.x, winding: -1.0 }) }
+    }).collect()
+}
+
+/// Append the two triangles of the trapezoid bounded by `left` and `right` between `y0` and `y1`.
+fn push_trapezoid(triangles: &mut Vec<[Point; 3]>, left: &PolygonEdge, right: &PolygonEdge, y0: f32, y1: f32) {
+    let top_left = Point::new(left.x_at(y0), y0);
+    let top_right = Point::new(right.x_at(y0), y0);
+    let bottom_left = Point::new(left.x_at(y1), y1);
+    let bottom_right = Point::new(right.x_at(y1), y1);
+
+    triangles.push([top_left, top_right, bottom_right]);
+    triangles.push([top_left, bottom_right, bottom_left]);
+}
+
+/// Tessellate the closed polygon `points` into triangles, selecting which spans are "inside" according
+/// to `fill_rule` (as in the Ruffle shape tessellator). This is a trapezoidal/scanline decomposition: the
+/// polygon is sliced at every vertex's `y`, and each slice is further cut at any point where two active
+/// edges cross, since a self-intersecting or overlapping path (which the nonzero rule exists to support)
+/// can swap two edges' x-order mid-slice; within each resulting sub-slice the edges are sorted by `x` and
+/// paired off into trapezoids, which are then split into two triangles each.
+fn tessellate_fill(points: &[Point], fill_rule: FillRule) -> Vec<[Point; 3]> {
+    let edges = polygon_edges(points);
+    if edges.is_empty() { return Vec::new(); }
+
+    let mut ys: Vec<f32> = edges.iter().flat_map(|e| [e.y0, e.y1]).collect();
+    ys.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    ys.dedup();
+
+    let mut triangles = Vec::new();
+
+    for window in ys.windows(2) {
+        let (y0, y1) = (window[0], window[1]);
+
+        let active: Vec<&PolygonEdge> = edges.iter().filter(|e| e.y0 <= y0 && e.y1 >= y1).collect();
+
+        let mut breaks = vec![y0, y1];
+        for i in 0..active.len() {
+            for j in (i + 1)..active.len() {
+                if let Some(y) = edge_crossing(active[i], active[j], y0, y1) { breaks.push(y); }
+            }
+        }
+        breaks.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        breaks.dedup();
+
+        for sub in breaks.windows(2) {
+            let (sy0, sy1) = (sub[0], sub[1]);
+            let mid = (sy0 + sy1) / 2.0; // sample inside the sub-slice so a crossing at its endpoint doesn't tie
+            let mut sorted = active.clone();
+            sorted.sort_by(|a, b| a.x_at(mid).partial_cmp(&b.x_at(mid)).unwrap());
+
+            match fill_rule {
+                FillRule::EvenOdd => {
+                    for pair in sorted.chunks(2) {
+                        if let [left, right] = pair { push_trapezoid(&mut triangles, left, right, sy0, sy1); }
+                    }
+                }
+                FillRule::NonZero => {
+                    let mut winding = 0.0;
+                    for pair in sorted.windows(2) {
+                        winding += pair[0].winding;
+                        if winding != 0.0 { push_trapezoid(&mut triangles, pair[0], pair[1], sy0, sy1); }
+                    }
+                }
+            }
+        }
+    }
+
+    triangles
+}
+
+/// The contents of every `d="..."` attribute in an SVG document, in order.
+fn extract_path_data(svg: &str) -> Vec<String> {
+    const MARKER: &str = "d=\"";
+
+    let mut paths = Vec::new();
+    let mut rest = svg;
+    while let Some(start) = rest.find(MARKER) {
+        let after = &rest[start + MARKER.len()..];
+        match after.find('"') {
+            Some(end) => {
+                paths.push(after[..end].to_string());
+                rest = &after[end + 1..];
+            }
+            None => break,
+        }
+    }
+    paths
+}
+
+/// Axis-aligned bounding box of a point set. Panics if `points` is empty.
+fn bounding_box_of_points(points: &[Point]) -> Box {
+    let mut b = Box { p1: points[0], p2: points[0] };
+    for &p in &points[1..] { b.expand_to_contain(p); }
+    b
 }
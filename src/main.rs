@@ -11,7 +11,10 @@ Canvas
 mod canvas;
 
 use macroquad::prelude::*;
-use canvas::{Canvas, Point};
+use canvas::{Canvas, FillRule, Point};
+
+/// File strokes are saved to and loaded from via the `S`/`O` keys.
+const SAVE_PATH: &str = "canvas.svg";
 
 #[macroquad::main("test window")] // window name
 async fn main() {
@@ -45,7 +48,35 @@ async fn main() {
             else { canvas.begin_erasure(mouse_pos).unwrap(); };
         }
         else if is_mouse_button_released(MouseButton::Right) { // mouse button just released this frame
-            if canvas.is_stroke_in_progress() { canvas.end_erasure().unwrap(); }
+            if canvas.is_erasure_in_progress() { canvas.end_erasure().unwrap(); }
+        }
+
+        // F closes and fills the most recently drawn curve; hold shift for the nonzero winding rule
+        if is_key_pressed(KeyCode::F) {
+            let fill_rule = if is_key_down(KeyCode::LeftShift) { FillRule::NonZero } else { FillRule::EvenOdd };
+            let _ = canvas.close_last_curve(fill_rule);
+        }
+
+        // S saves the canvas to SVG, O loads it back
+        if is_key_pressed(KeyCode::S) {
+            std::fs::write(SAVE_PATH, canvas.to_svg()).unwrap();
+        }
+        if is_key_pressed(KeyCode::O) {
+            match std::fs::read_to_string(SAVE_PATH) {
+                Ok(svg) => match Canvas::from_svg(&svg) {
+                    Ok(loaded) => canvas = loaded,
+                    Err(e) => dbg_string.push_str(format!(" (load failed: {e})").as_str()),
+                },
+                Err(e) => dbg_string.push_str(format!(" (read failed: {e})").as_str()),
+            }
+        }
+
+        // show how many curves the spatial index finds near the cursor
+        #[cfg(debug_assertions)]
+        {
+            let cursor_region = canvas::Box::new(mouse_pos - Vec2::splat(10.0), mouse_pos + Vec2::splat(10.0));
+            let nearby = canvas.curves_intersecting(&cursor_region).count();
+            dbg_string.push_str(format!(" nearby:{nearby}").as_str());
         }
 
         // render